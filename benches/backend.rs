@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use rocksdb::WriteBatch;
+
+/// A minimal embedded-store interface covering the access patterns this suite benchmarks, so the
+/// same `generate_key`/`generate_value` workload can be driven against more than one engine.
+pub trait Backend {
+    fn open(path: &Path) -> Self
+    where
+        Self: Sized;
+    fn write_batch(&self, entries: &[(Vec<u8>, Vec<u8>)]);
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>>;
+    fn range_iter(&self, start: &[u8], end: &[u8]) -> usize;
+}
+
+impl Backend for rocksdb::DB {
+    fn open(path: &Path) -> Self {
+        rocksdb::DB::open_default(path).unwrap()
+    }
+
+    fn write_batch(&self, entries: &[(Vec<u8>, Vec<u8>)]) {
+        let mut batch = WriteBatch::default();
+        for (key, value) in entries {
+            batch.put(key, value);
+        }
+        self.write(batch).unwrap();
+    }
+
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.get(key).unwrap()
+    }
+
+    fn range_iter(&self, start: &[u8], end: &[u8]) -> usize {
+        self.iterator(rocksdb::IteratorMode::From(start, rocksdb::Direction::Forward))
+            .take_while(|result| matches!(result, Ok((k, _)) if k.as_ref() < end))
+            .count()
+    }
+}
+
+#[cfg(feature = "sled")]
+impl Backend for sled::Db {
+    fn open(path: &Path) -> Self {
+        sled::open(path).unwrap()
+    }
+
+    fn write_batch(&self, entries: &[(Vec<u8>, Vec<u8>)]) {
+        let mut batch = sled::Batch::default();
+        for (key, value) in entries {
+            batch.insert(key.as_slice(), value.as_slice());
+        }
+        self.apply_batch(batch).unwrap();
+    }
+
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        sled::Tree::get(self, key).unwrap().map(|v| v.to_vec())
+    }
+
+    fn range_iter(&self, start: &[u8], end: &[u8]) -> usize {
+        self.range(start.to_vec()..end.to_vec()).count()
+    }
+}
@@ -1,9 +1,16 @@
+mod backend;
+
 use std::path::Path;
 use std::sync::LazyLock;
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
 use rand::Rng;
-use rocksdb::{ColumnFamilyDescriptor, Options, WriteBatch, DB};
+use rocksdb::{
+    BlockBasedOptions, ColumnFamilyDescriptor, DBCompressionType, OptimisticTransactionDB,
+    Options, SliceTransform, TransactionDB, TransactionDBOptions, WriteBatch, DB,
+};
+
+use backend::Backend;
 
 // RocksDB Defaults
 pub static ROCKSDB_THREAD_COUNT: LazyLock<i32> = LazyLock::new(|| num_cpus::get() as i32);
@@ -18,6 +25,16 @@ pub static ROCKSDB_KEEP_LOG_FILE_NUM: usize = 20;
 const NUM_TABLES: usize = 10;
 const NUM_KEYS_PER_TABLE: usize = 10000;
 
+// Transaction workload tuning knobs.
+const TXN_THREADS: usize = 4;
+const TXNS_PER_THREAD: usize = 50;
+const KEYS_PER_TXN: usize = 8;
+const HOT_KEY_POOL_SIZE: usize = 8;
+// Fraction of a transaction's keys drawn from the small `HOT_KEY_POOL_SIZE` pool rather than
+// spread across the whole table. Higher values mean more concurrent transactions touch the same
+// keys, so pessimistic locking blocks more and the optimistic path retries more at commit time.
+const CONFLICT_RATIO: f64 = 0.5;
+
 fn generate_key(table: usize, x: usize) -> String {
     format!("/table/{:02}/{:05}", table, x)
 }
@@ -139,6 +156,39 @@ fn range_query_multiple_dbs(dbs: &[DB], table: usize) {
     );
 }
 
+// Seeks directly to a table's prefix and iterates only within it, letting the prefix extractor
+// and bloom filter skip SST blocks that can't contain a match instead of scanning from the start.
+fn prefix_seek_single_cf(db: &DB, table: usize) {
+    let prefix = format!("/table/{:02}/", table);
+    let end_key = generate_key(table, NUM_KEYS_PER_TABLE);
+
+    let count = black_box({
+        db.prefix_iterator(prefix.as_bytes())
+            .take_while(|result| match result {
+                Ok((k, _)) => k.as_ref() < end_key.as_bytes(),
+                Err(_) => false,
+            })
+            .count()
+    });
+
+    assert_eq!(
+        count, NUM_KEYS_PER_TABLE,
+        "Count of items does not match NUM_KEYS_PER_TABLE"
+    );
+}
+
+// Looks up keys that are guaranteed not to exist, so a bloom filter should let RocksDB skip the
+// SST read entirely instead of probing disk for a miss.
+fn negative_get(db: &DB) {
+    let mut rng = rand::thread_rng();
+    for _ in 0..1000 {
+        let table = rng.gen_range(0..NUM_TABLES);
+        let x = NUM_KEYS_PER_TABLE + rng.gen_range(0..NUM_KEYS_PER_TABLE);
+        let key = generate_key(table, x);
+        black_box(db.get(key.as_bytes()).unwrap());
+    }
+}
+
 fn get_single_cf(db: &DB) {
     let mut rng = rand::thread_rng();
     for _ in 0..1000 {
@@ -172,6 +222,109 @@ fn get_multiple_dbs(dbs: &[DB]) {
     }
 }
 
+fn multi_get_single_cf(db: &DB) {
+    let mut rng = rand::thread_rng();
+    let keys: Vec<String> = (0..1000)
+        .map(|_| {
+            let table = rng.gen_range(0..NUM_TABLES);
+            let x = rng.gen_range(0..NUM_KEYS_PER_TABLE);
+            generate_key(table, x)
+        })
+        .collect();
+    black_box(db.multi_get(keys.iter().map(String::as_bytes)));
+}
+
+fn multi_get_multiple_cf(db: &DB) {
+    let mut rng = rand::thread_rng();
+    let cf_keys: Vec<_> = (0..1000)
+        .map(|_| {
+            let table = rng.gen_range(0..NUM_TABLES);
+            let x = rng.gen_range(0..NUM_KEYS_PER_TABLE);
+            let cf = db.cf_handle(&format!("table_{}", table)).unwrap();
+            (cf, generate_key(table, x).into_bytes())
+        })
+        .collect();
+    black_box(db.multi_get_cf(cf_keys.iter().map(|(cf, key)| (cf, key.as_slice()))));
+}
+
+fn multi_get_multiple_dbs(dbs: &[DB]) {
+    let mut rng = rand::thread_rng();
+    let mut keys_by_db: Vec<Vec<String>> = vec![Vec::new(); NUM_TABLES];
+    for _ in 0..1000 {
+        let table = rng.gen_range(0..NUM_TABLES);
+        let x = rng.gen_range(0..NUM_KEYS_PER_TABLE);
+        keys_by_db[table].push(generate_key(table, x));
+    }
+    for (table, keys) in keys_by_db.iter().enumerate() {
+        if keys.is_empty() {
+            continue;
+        }
+        black_box(dbs[table].multi_get(keys.iter().map(String::as_bytes)));
+    }
+}
+
+fn populate_table(db: &DB, table: usize) {
+    let mut batch = WriteBatch::default();
+    for x in 0..NUM_KEYS_PER_TABLE {
+        let key = generate_key(table, x);
+        let value = generate_value();
+        batch.put(key.as_bytes(), value.as_bytes());
+    }
+    db.write(batch).unwrap();
+    // `delete_file_in_range` only drops SSTs, so the written keys must be flushed out of the
+    // memtable before it runs or it finds nothing to do.
+    db.flush().unwrap();
+}
+
+// Writes the same table twice so the second write overlaps the first in separate SSTs, leaving
+// the table fragmented and giving a subsequent compaction real merge work to do.
+fn fragment_table(db: &DB, table: usize) {
+    populate_table(db, table);
+    populate_table(db, table);
+}
+
+fn compact_table(db: &DB, table: usize) {
+    let start_key = generate_key(table, 0);
+    let end_key = generate_key(table, NUM_KEYS_PER_TABLE);
+    db.compact_range(Some(start_key.as_bytes()), Some(end_key.as_bytes()));
+}
+
+fn report_live_files(db: &DB) -> usize {
+    black_box(db.live_files().unwrap().len())
+}
+
+// One-time report of the SST layout; not itself timed, just printed alongside the live-files
+// enumeration benchmark so the level/size numbers are visible next to the throughput.
+fn print_live_files_level_distribution(db: &DB) {
+    let mut per_level: std::collections::BTreeMap<i32, (usize, u64)> =
+        std::collections::BTreeMap::new();
+    for file in db.live_files().unwrap() {
+        let entry = per_level.entry(file.level).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 += file.size as u64;
+    }
+    for (level, (count, size)) in per_level {
+        println!("level={} files={} bytes={}", level, count, size);
+    }
+}
+
+// Drops an entire `/table/NN/` prefix at the file level: RocksDB can discard whole SSTs that fall
+// inside the range instead of rewriting them with tombstones.
+fn delete_range_bulk(db: &DB, table: usize) {
+    let start_key = generate_key(table, 0);
+    let end_key = generate_key(table, NUM_KEYS_PER_TABLE);
+    db.delete_file_in_range(start_key.as_bytes(), end_key.as_bytes())
+        .unwrap();
+}
+
+fn delete_range_one_by_one(db: &DB, table: usize) {
+    let mut batch = WriteBatch::default();
+    for x in 0..NUM_KEYS_PER_TABLE {
+        batch.delete(generate_key(table, x).as_bytes());
+    }
+    db.write(batch).unwrap();
+}
+
 fn open_multiple_dbs(path: &Path) -> Vec<DB> {
     let mut dbs = Vec::with_capacity(NUM_TABLES);
     for table in 0..NUM_TABLES {
@@ -182,6 +335,277 @@ fn open_multiple_dbs(path: &Path) -> Vec<DB> {
     dbs
 }
 
+// Picks the keys touched by a single transaction within `table`. Each key independently has a
+// `CONFLICT_RATIO` chance of landing in the shared `HOT_KEY_POOL_SIZE` pool so concurrent
+// transactions collide, and otherwise falls anywhere in the table so some transactions never
+// conflict at all.
+fn pick_txn_keys(table: usize, rng: &mut impl Rng) -> Vec<String> {
+    (0..KEYS_PER_TXN)
+        .map(|i| {
+            let x = if rng.gen_bool(CONFLICT_RATIO) {
+                i % HOT_KEY_POOL_SIZE
+            } else {
+                rng.gen_range(0..NUM_KEYS_PER_TABLE)
+            };
+            generate_key(table, x)
+        })
+        .collect()
+}
+
+// Associative merge operator for an i64 counter: sums every pending operand onto the existing
+// value, treating a missing base value as zero.
+fn counter_merge(
+    _key: &[u8],
+    existing_val: Option<&[u8]>,
+    operands: &rocksdb::MergeOperands,
+) -> Option<Vec<u8>> {
+    let mut acc: i64 = existing_val
+        .map(|v| i64::from_le_bytes(v.try_into().unwrap()))
+        .unwrap_or(0);
+    for operand in operands {
+        acc += i64::from_le_bytes(operand.try_into().unwrap());
+    }
+    Some(acc.to_le_bytes().to_vec())
+}
+
+fn make_opts_with_merge_operator() -> Options {
+    let mut opts = make_opts();
+    opts.set_merge_operator_associative("counter_add", counter_merge);
+    opts
+}
+
+fn merge_counter(db: &DB) {
+    let mut rng = rand::thread_rng();
+    for _ in 0..1000 {
+        let table = rng.gen_range(0..NUM_TABLES);
+        let x = rng.gen_range(0..NUM_KEYS_PER_TABLE);
+        let key = generate_key(table, x);
+        let delta: i64 = rng.gen_range(1..100);
+        db.merge(key.as_bytes(), delta.to_le_bytes()).unwrap();
+    }
+}
+
+fn get_modify_put_counter(db: &DB) {
+    let mut rng = rand::thread_rng();
+    for _ in 0..1000 {
+        let table = rng.gen_range(0..NUM_TABLES);
+        let x = rng.gen_range(0..NUM_KEYS_PER_TABLE);
+        let key = generate_key(table, x);
+        let delta: i64 = rng.gen_range(1..100);
+        let current = db
+            .get(key.as_bytes())
+            .unwrap()
+            .map(|v| i64::from_le_bytes(v.as_slice().try_into().unwrap()))
+            .unwrap_or(0);
+        db.put(key.as_bytes(), (current + delta).to_le_bytes())
+            .unwrap();
+    }
+}
+
+// Drives the same key/value workload through any `Backend` implementation so engines can be
+// compared apples-to-apples in one Criterion group.
+fn bench_backend<B: Backend>(c: &mut Criterion, name: &str, backend: &B) {
+    let mut group = c.benchmark_group(format!("backend_{}", name));
+
+    let entries: Vec<(Vec<u8>, Vec<u8>)> = (0..NUM_TABLES)
+        .flat_map(|table| {
+            (0..NUM_KEYS_PER_TABLE)
+                .map(move |x| (generate_key(table, x).into_bytes(), generate_value().into_bytes()))
+        })
+        .collect();
+
+    group.bench_function("write", |b| b.iter(|| backend.write_batch(&entries)));
+
+    group.bench_function("get", |b| {
+        let mut rng = rand::thread_rng();
+        b.iter(|| {
+            let table = rng.gen_range(0..NUM_TABLES);
+            let x = rng.gen_range(0..NUM_KEYS_PER_TABLE);
+            black_box(backend.get(generate_key(table, x).as_bytes()))
+        })
+    });
+
+    group.bench_function("range_iter", |b| {
+        let start_key = generate_key(5, 0);
+        let end_key = generate_key(5, NUM_KEYS_PER_TABLE);
+        b.iter(|| black_box(backend.range_iter(start_key.as_bytes(), end_key.as_bytes())))
+    });
+
+    group.finish();
+}
+
+fn make_opts_with_statistics() -> Options {
+    let mut opts = make_opts();
+    opts.enable_statistics();
+    opts.set_statistics_level(rocksdb::statistics::StatsLevel::ExceptDetailedTimers);
+    opts
+}
+
+// RocksDB prints each ticker as "<name> COUNT : <value>" in the statistics dump; pull out the
+// handful of counters this benchmark cares about rather than the whole block of text.
+fn parse_ticker(stats: &str, name: &str) -> Option<u64> {
+    stats
+        .lines()
+        .find(|line| line.starts_with(name))
+        .and_then(|line| line.split("COUNT : ").nth(1))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+// Correlates a group's throughput with the write-stall and cache behavior behind it, instead of
+// treating the engine as a black box.
+fn report_rocksdb_counters(db: &DB, opts: &Options, label: &str) {
+    if let Some(stats) = opts.get_statistics() {
+        for ticker in [
+            "rocksdb.block.cache.hit",
+            "rocksdb.block.cache.miss",
+            "rocksdb.bytes.written",
+            "rocksdb.compact.write.bytes",
+            "rocksdb.stall.micros",
+        ] {
+            if let Some(value) = parse_ticker(&stats, ticker) {
+                println!("[{}] {} = {}", label, ticker, value);
+            }
+        }
+    }
+
+    if let Ok(Some(cache_usage)) = db.property_int_value("rocksdb.block-cache-usage") {
+        println!("[{}] rocksdb.block-cache-usage = {}", label, cache_usage);
+    }
+}
+
+// Matches the fixed-width 9-byte "/table/NN" prefix produced by `generate_key` so RocksDB can prefix-seek
+// and filter SST blocks with a bloom filter instead of scanning from the start of the CF.
+fn make_opts_with_prefix_extractor() -> Options {
+    let mut opts = make_opts();
+    opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(9));
+
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_bloom_filter(10.0, false);
+    opts.set_block_based_table_factory(&block_opts);
+
+    opts
+}
+
+fn make_opts_with_compression(compression: DBCompressionType) -> Options {
+    let mut opts = make_opts();
+    opts.set_compression_type(compression);
+    opts
+}
+
+fn full_scan(db: &DB) -> usize {
+    black_box(
+        db.iterator(rocksdb::IteratorMode::Start)
+            .filter(Result::is_ok)
+            .count(),
+    )
+}
+
+fn live_files_size(db: &DB) -> u64 {
+    db.live_files()
+        .unwrap()
+        .iter()
+        .map(|f| f.size as u64)
+        .sum()
+}
+
+// Benchmarks writes of the full workload under one compression algorithm, flushes what they wrote
+// to disk and reports the resulting SST size, then benchmarks a full scan under that algorithm.
+fn bench_compression_variant(
+    group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    name: &str,
+    compression: DBCompressionType,
+    base_path: &Path,
+) {
+    let opts = make_opts_with_compression(compression);
+    let db = DB::open(&opts, base_path.join(format!("compression_{}", name))).unwrap();
+
+    group.bench_function(BenchmarkId::new("write", name), |b| {
+        b.iter(|| write_single_cf(&db))
+    });
+
+    db.flush().unwrap();
+    println!(
+        "compression={} on-disk bytes={}",
+        name,
+        live_files_size(&db)
+    );
+
+    group.bench_function(BenchmarkId::new("full_scan", name), |b| {
+        b.iter(|| full_scan(&db))
+    });
+}
+
+fn open_transaction_db(path: &Path) -> TransactionDB {
+    TransactionDB::open(&make_opts(), &TransactionDBOptions::default(), path).unwrap()
+}
+
+fn open_optimistic_transaction_db(path: &Path) -> OptimisticTransactionDB {
+    OptimisticTransactionDB::open(&make_opts(), path).unwrap()
+}
+
+// Pessimistic locking: `get_for_update` takes a row lock up front, so a conflicting transaction
+// blocks until the lock holder commits rather than failing at commit time.
+fn write_pessimistic_txn(db: &TransactionDB, table: usize) {
+    std::thread::scope(|s| {
+        for _ in 0..TXN_THREADS {
+            s.spawn(|| {
+                let mut rng = rand::thread_rng();
+                for _ in 0..TXNS_PER_THREAD {
+                    let keys = pick_txn_keys(table, &mut rng);
+                    // `transaction_lock_timeout` is finite, so under the contention this workload
+                    // is built to create, `get_for_update` can legitimately come back Busy/TimedOut
+                    // rather than block forever. Drop the attempt and retry from scratch, same as
+                    // the optimistic path does on a commit conflict.
+                    'retry: loop {
+                        let txn = db.transaction();
+                        for key in &keys {
+                            if txn.get_for_update(key.as_bytes(), true).is_err() {
+                                continue 'retry;
+                            }
+                        }
+                        for key in &keys {
+                            txn.put(key.as_bytes(), generate_value().as_bytes()).unwrap();
+                        }
+                        match txn.commit() {
+                            Ok(()) => break,
+                            Err(_) => continue,
+                        }
+                    }
+                }
+            });
+        }
+    });
+}
+
+// Optimistic CAS: no locks are taken while reading, so a conflicting write is only detected when
+// the transaction commits, and the loser must retry the whole transaction.
+fn write_optimistic_txn(db: &OptimisticTransactionDB, table: usize) {
+    std::thread::scope(|s| {
+        for _ in 0..TXN_THREADS {
+            s.spawn(|| {
+                let mut rng = rand::thread_rng();
+                for _ in 0..TXNS_PER_THREAD {
+                    let keys = pick_txn_keys(table, &mut rng);
+                    loop {
+                        let txn = db.transaction();
+                        for key in &keys {
+                            txn.get_for_update(key.as_bytes(), true).unwrap();
+                        }
+                        for key in &keys {
+                            txn.put(key.as_bytes(), generate_value().as_bytes()).unwrap();
+                        }
+                        match txn.commit() {
+                            Ok(()) => break,
+                            Err(_) => continue,
+                        }
+                    }
+                }
+            });
+        }
+    });
+}
+
 fn make_opts() -> Options {
     let mut opts = Options::default();
     opts.create_if_missing(true);
@@ -232,6 +656,57 @@ fn benchmark(c: &mut Criterion) {
     // Multiple DBs setup
     let dbs_multiple = open_multiple_dbs(path);
 
+    // Transactional setup
+    let db_pessimistic = open_transaction_db(&path.join("txn_pessimistic"));
+    let db_optimistic = open_optimistic_transaction_db(&path.join("txn_optimistic"));
+
+    // Merge-operator counter setup
+    let db_merge = DB::open(
+        &make_opts_with_merge_operator(),
+        path.join("merge_counter"),
+    )
+    .unwrap();
+    let db_get_modify_put = DB::open(
+        &make_opts_with_merge_operator(),
+        path.join("get_modify_put_counter"),
+    )
+    .unwrap();
+
+    // Prefix-seek setup
+    let db_prefix = DB::open(&make_opts_with_prefix_extractor(), path.join("prefix_seek")).unwrap();
+    write_single_cf(&db_prefix);
+
+    // File-management setup: one DB pre-populated with the full 10x10000 layout; the delete
+    // benchmarks repopulate table 7 before each iteration.
+    let db_file_mgmt = DB::open_default(path.join("file_mgmt")).unwrap();
+    write_single_cf(&db_file_mgmt);
+
+    // Pluggable-backend matrix: every enabled engine runs the identical workload.
+    let rocksdb_backend = <DB as Backend>::open(&path.join("backend_rocksdb"));
+    bench_backend(c, "rocksdb", &rocksdb_backend);
+
+    #[cfg(feature = "sled")]
+    {
+        let sled_backend = <sled::Db as Backend>::open(&path.join("backend_sled"));
+        bench_backend(c, "sled", &sled_backend);
+    }
+
+    // Instrumented setup: the same write/get workload, but with statistics enabled so the
+    // resulting counters can be read alongside the timings.
+    let instrumented_opts = make_opts_with_statistics();
+    let db_instrumented = DB::open(&instrumented_opts, path.join("instrumented")).unwrap();
+
+    let mut instrumented_group = c.benchmark_group("instrumented");
+    instrumented_group.bench_function("write_single_cf", |b| {
+        b.iter(|| write_single_cf(&db_instrumented))
+    });
+    instrumented_group.bench_function("get_single_cf", |b| {
+        b.iter(|| get_single_cf(&db_instrumented))
+    });
+    instrumented_group.finish();
+
+    report_rocksdb_counters(&db_instrumented, &instrumented_opts, "instrumented");
+
     c.bench_function("write_single_cf", |b| {
         b.iter(|| write_single_cf(&db_single))
     });
@@ -259,6 +734,107 @@ fn benchmark(c: &mut Criterion) {
     c.bench_function("get_multiple_dbs", |b| {
         b.iter(|| get_multiple_dbs(&dbs_multiple))
     });
+
+    c.bench_function("multi_get_single_cf", |b| {
+        b.iter(|| multi_get_single_cf(&db_single))
+    });
+    c.bench_function("multi_get_multiple_cf", |b| {
+        b.iter(|| multi_get_multiple_cf(&db_multiple))
+    });
+    c.bench_function("multi_get_multiple_dbs", |b| {
+        b.iter(|| multi_get_multiple_dbs(&dbs_multiple))
+    });
+
+    c.bench_function("write_pessimistic_txn", |b| {
+        b.iter(|| write_pessimistic_txn(&db_pessimistic, 5))
+    });
+    c.bench_function("write_optimistic_txn", |b| {
+        b.iter(|| write_optimistic_txn(&db_optimistic, 5))
+    });
+
+    c.bench_function("merge_counter", |b| b.iter(|| merge_counter(&db_merge)));
+    c.bench_function("get_modify_put_counter", |b| {
+        b.iter(|| get_modify_put_counter(&db_get_modify_put))
+    });
+
+    c.bench_function("prefix_seek_single_cf", |b| {
+        b.iter(|| prefix_seek_single_cf(&db_prefix, 5))
+    });
+    c.bench_function("negative_get_single_cf", |b| {
+        b.iter(|| negative_get(&db_single))
+    });
+    c.bench_function("negative_get_with_bloom", |b| {
+        b.iter(|| negative_get(&db_prefix))
+    });
+
+    c.bench_function("compact_range_full", |b| {
+        b.iter_batched(
+            || fragment_table(&db_file_mgmt, 8),
+            |_| compact_table(&db_file_mgmt, 8),
+            BatchSize::LargeInput,
+        )
+    });
+
+    print_live_files_level_distribution(&db_file_mgmt);
+    c.bench_function("live_files_metadata", |b| {
+        b.iter(|| report_live_files(&db_file_mgmt))
+    });
+
+    c.bench_function("delete_range_bulk", |b| {
+        b.iter_batched(
+            || populate_table(&db_file_mgmt, 7),
+            |_| delete_range_bulk(&db_file_mgmt, 7),
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("delete_range_one_by_one", |b| {
+        b.iter_batched(
+            || populate_table(&db_file_mgmt, 7),
+            |_| delete_range_one_by_one(&db_file_mgmt, 7),
+            BatchSize::SmallInput,
+        )
+    });
+
+    // Compression backends are sized and timed in a shared group so write throughput and the
+    // resulting on-disk size can be read side by side.
+    let mut compression_group = c.benchmark_group("compression");
+    bench_compression_variant(
+        &mut compression_group,
+        "none",
+        DBCompressionType::None,
+        path,
+    );
+    #[cfg(feature = "snappy")]
+    bench_compression_variant(
+        &mut compression_group,
+        "snappy",
+        DBCompressionType::Snappy,
+        path,
+    );
+    #[cfg(feature = "lz4")]
+    bench_compression_variant(&mut compression_group, "lz4", DBCompressionType::Lz4, path);
+    #[cfg(feature = "zlib")]
+    bench_compression_variant(
+        &mut compression_group,
+        "zlib",
+        DBCompressionType::Zlib,
+        path,
+    );
+    #[cfg(feature = "zstd")]
+    bench_compression_variant(
+        &mut compression_group,
+        "zstd",
+        DBCompressionType::Zstd,
+        path,
+    );
+    #[cfg(feature = "bzip2")]
+    bench_compression_variant(
+        &mut compression_group,
+        "bzip2",
+        DBCompressionType::Bz2,
+        path,
+    );
+    compression_group.finish();
 }
 
 criterion_group!(benches, benchmark);